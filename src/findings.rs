@@ -0,0 +1,89 @@
+use crate::{DetectiveError, MimeDetective};
+use std::path::{Path, PathBuf};
+
+/// The result of checking a file's detected content against its extension.
+///
+/// Lets callers spot misnamed files (e.g. a `.jpg` that is actually a PNG)
+/// instead of just sniffing the raw Mime.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Findings {
+    pub path: PathBuf,
+    pub detected_mime: mime::Mime,
+    pub valid: bool,
+    pub recommended_extension: Option<String>,
+}
+
+impl Findings {
+    /// Build `Findings` for a path whose Mime has already been detected,
+    /// comparing it against the Mimes the path's current extension would
+    /// suggest.
+    ///
+    /// Pure path/Mime logic with no libmagic involved, so callers that have
+    /// already detected a Mime some other way can still get a validity
+    /// verdict.
+    pub(crate) fn from_detected(path: PathBuf, detected_mime: mime::Mime) -> Findings {
+        let valid = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| {
+                mime_guess::from_ext(ext)
+                    .iter()
+                    .any(|candidate| candidate == detected_mime)
+            })
+            .unwrap_or(false);
+
+        let recommended_extension = mime_guess::get_mime_extensions(&detected_mime)
+            .and_then(|extensions| extensions.first())
+            .map(|extension| extension.to_string());
+
+        Findings {
+            path,
+            detected_mime,
+            valid,
+            recommended_extension,
+        }
+    }
+}
+
+impl MimeDetective {
+    /// Detect a filepath's Mime and compare it against the Mimes its current
+    /// extension would suggest.
+    ///
+    /// `valid` is `true` when the file's current extension is among the
+    /// candidates for its detected Mime. `recommended_extension` is the
+    /// extension `mime_guess` would pick for the detected Mime.
+    pub fn detect_findings<P: AsRef<Path>>(
+        &self,
+        filename: P,
+    ) -> Result<Findings, DetectiveError> {
+        let path = filename.as_ref().to_path_buf();
+        let detected_mime = self.detect_filepath(&path)?;
+        Ok(Findings::from_detected(path, detected_mime))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Findings;
+    use std::path::PathBuf;
+
+    #[test]
+    fn valid_extension_matches_detected_mime() {
+        let findings = Findings::from_detected(PathBuf::from("photo.jpg"), mime::IMAGE_JPEG);
+        assert!(findings.valid);
+        assert_eq!(findings.recommended_extension.as_deref(), Some("jpg"));
+    }
+
+    #[test]
+    fn mismatched_extension_is_invalid_with_recommendation() {
+        let findings = Findings::from_detected(PathBuf::from("photo.jpg"), mime::IMAGE_PNG);
+        assert!(!findings.valid);
+        assert_eq!(findings.recommended_extension.as_deref(), Some("png"));
+    }
+
+    #[test]
+    fn missing_extension_is_invalid() {
+        let findings = Findings::from_detected(PathBuf::from("README"), mime::TEXT_PLAIN);
+        assert!(!findings.valid);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{Findings, ScanError};
+use std::ffi::OsStr;
+use std::path::Path;
+
+/// Renders scan results (misnamed files, unreadable files, undetectable
+/// files) into some output format.
+pub trait Format {
+    /// Render a rename of a misnamed file from its current path to the
+    /// recommended one.
+    fn rename(&self, from: &Path, to: &Path) -> Vec<u8>;
+    /// Render a note about a file that couldn't be read.
+    fn unreadable(&self, path: &Path) -> Vec<u8>;
+    /// Render a note about a file libmagic couldn't classify, or a misnamed
+    /// file whose detected Mime has no known extension to rename it to.
+    fn unknown_type(&self, path: &Path) -> Vec<u8>;
+    /// Render a generic scan error with no associated path, such as a
+    /// poisoned detective.
+    fn error(&self, message: &str) -> Vec<u8>;
+}
+
+/// Formats scan results as a POSIX shell script of `mv` commands, so a
+/// scan's fixes can be applied in one shot with `sh fixup.sh`.
+pub struct ShellScript;
+
+impl Format for ShellScript {
+    fn rename(&self, from: &Path, to: &Path) -> Vec<u8> {
+        let mut line = b"mv ".to_vec();
+        line.extend(shell_quote(from.as_os_str()));
+        line.push(b' ');
+        line.extend(shell_quote(to.as_os_str()));
+        line
+    }
+
+    fn unreadable(&self, path: &Path) -> Vec<u8> {
+        let mut line = b"# unreadable: ".to_vec();
+        line.extend(shell_quote(path.as_os_str()));
+        line
+    }
+
+    fn unknown_type(&self, path: &Path) -> Vec<u8> {
+        let mut line = b"# unknown type: ".to_vec();
+        line.extend(shell_quote(path.as_os_str()));
+        line
+    }
+
+    fn error(&self, message: &str) -> Vec<u8> {
+        format!("# {}", message).into_bytes()
+    }
+}
+
+/// Render a sequence of [`scan`](struct.MimeDetective.html#method.scan)
+/// results into a script, one line per file that needs attention.
+///
+/// Files whose extension already matches their detected type produce no
+/// output.
+pub fn render<F: Format>(formatter: &F, results: &[Result<Findings, ScanError>]) -> Vec<u8> {
+    let mut script = Vec::new();
+    for result in results {
+        let line = match result {
+            Ok(findings) if !findings.valid => match &findings.recommended_extension {
+                Some(extension) => {
+                    let to = findings.path.with_extension(extension);
+                    Some(formatter.rename(&findings.path, &to))
+                }
+                None => Some(formatter.unknown_type(&findings.path)),
+            },
+            Ok(_) => None,
+            Err(ScanError::Read(path, _)) => Some(formatter.unreadable(path)),
+            Err(ScanError::Undetectable(path)) => Some(formatter.unknown_type(path)),
+            Err(ScanError::Poisoned) => Some(formatter.error("detective poisoned; aborting")),
+        };
+        if let Some(line) = line {
+            script.extend(line);
+            script.push(b'\n');
+        }
+    }
+    script
+}
+
+/// Single-quote `s` for safe inclusion in a POSIX shell command, escaping
+/// embedded single quotes. On unix this operates on the raw bytes of the
+/// `OsStr` so non-UTF-8 paths round-trip untouched; elsewhere it falls back
+/// to a lossy UTF-8 conversion.
+#[cfg(unix)]
+fn shell_quote(s: &OsStr) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    quote_bytes(s.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn shell_quote(s: &OsStr) -> Vec<u8> {
+    quote_bytes(s.to_string_lossy().as_bytes())
+}
+
+fn quote_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut quoted = Vec::with_capacity(bytes.len() + 2);
+    quoted.push(b'\'');
+    for &b in bytes {
+        if b == b'\'' {
+            quoted.extend_from_slice(b"'\\''");
+        } else {
+            quoted.push(b);
+        }
+    }
+    quoted.push(b'\'');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, Format, ShellScript};
+    use crate::{Findings, ScanError};
+    use std::io;
+    use std::path::PathBuf;
+
+    #[test]
+    fn quote_bytes_escapes_embedded_single_quote() {
+        assert_eq!(super::quote_bytes(b"it's"), b"'it'\\''s'".to_vec());
+    }
+
+    #[test]
+    fn quote_bytes_wraps_plain_text() {
+        assert_eq!(super::quote_bytes(b"photo.jpg"), b"'photo.jpg'".to_vec());
+    }
+
+    #[test]
+    fn render_emits_rename_for_misnamed_file() {
+        let findings = Findings {
+            path: PathBuf::from("photo.jpg"),
+            detected_mime: mime::IMAGE_PNG,
+            valid: false,
+            recommended_extension: Some("png".to_string()),
+        };
+        let script = render(&ShellScript, &[Ok(findings)]);
+        assert_eq!(
+            String::from_utf8(script).unwrap(),
+            "mv 'photo.jpg' 'photo.png'\n"
+        );
+    }
+
+    #[test]
+    fn render_emits_nothing_for_valid_file() {
+        let findings = Findings {
+            path: PathBuf::from("photo.png"),
+            detected_mime: mime::IMAGE_PNG,
+            valid: true,
+            recommended_extension: Some("png".to_string()),
+        };
+        let script = render(&ShellScript, &[Ok(findings)]);
+        assert!(script.is_empty());
+    }
+
+    #[test]
+    fn render_emits_comment_for_unreadable_file() {
+        let err = io::Error::new(io::ErrorKind::PermissionDenied, "denied");
+        let script = render(
+            &ShellScript,
+            &[Err(ScanError::Read(PathBuf::from("secret"), err))],
+        );
+        assert_eq!(
+            String::from_utf8(script).unwrap(),
+            "# unreadable: 'secret'\n"
+        );
+    }
+
+    #[test]
+    fn render_emits_comment_for_undetectable_file() {
+        let script = render(
+            &ShellScript,
+            &[Err(ScanError::Undetectable(PathBuf::from("blob")))],
+        );
+        assert_eq!(
+            String::from_utf8(script).unwrap(),
+            "# unknown type: 'blob'\n"
+        );
+    }
+
+    #[test]
+    fn render_emits_comment_for_poisoned_detective() {
+        let script = render(&ShellScript, &[Err(ScanError::Poisoned)]);
+        assert_eq!(
+            String::from_utf8(script).unwrap(),
+            "# detective poisoned; aborting\n"
+        );
+    }
+}
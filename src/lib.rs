@@ -10,18 +10,44 @@
 //! let mime = detective.detect_filepath("Cargo.toml").unwrap();
 //! ```
 
+mod findings;
+mod format;
+mod scan;
+
+pub use crate::findings::Findings;
+pub use crate::format::{render, Format, ShellScript};
+pub use crate::scan::ScanError;
+
 use magic::{flags, Cookie, MagicError};
 use mime::FromStrError;
 use std::fs::File;
 use std::io::{self, Read};
 use std::path::Path;
+use std::sync::Mutex;
 use std::{error, fmt};
 
+/// Default number of leading bytes read by `detect_file`/`detect_reader`.
+///
+/// Large enough to cover most magic signatures (ZIP/OOXML, MP4 `ftyp`, etc.)
+/// without reading whole files.
+const DEFAULT_BUFFER_SIZE: usize = 1024;
+
 /// To detect the MimeType/ContentType using the magic library.
+///
+/// `libmagic` cookies aren't `Send`, so access to the underlying cookie is
+/// serialized behind a `Mutex`. This lets a single `MimeDetective` be wrapped
+/// in an `Arc` and shared across threads instead of opening one cookie per
+/// thread.
 pub struct MimeDetective {
-    cookie: Cookie,
+    cookie: Mutex<Cookie>,
+    buffer_size: usize,
 }
 
+// Safe because all access to `cookie` goes through the `Mutex`, which
+// guarantees exclusive access for the duration of each call into libmagic.
+unsafe impl Send for MimeDetective {}
+unsafe impl Sync for MimeDetective {}
+
 impl MimeDetective {
     /// Initialize detective with magic database from `/usr/share/misc/magic.mgc`.
     ///
@@ -31,12 +57,22 @@ impl MimeDetective {
     }
 
     /// Initialize detective with magic databases available at the provided path.
-    /// 
+    ///
     /// Requires system to have libmagic installed.
     pub fn load_databases<P: AsRef<Path>>(path: &[P]) -> Result<MimeDetective, DetectiveError> {
         let cookie = Cookie::open(flags::MIME_TYPE)?;
         cookie.load(path)?;
-        Ok(MimeDetective { cookie })
+        Ok(MimeDetective {
+            cookie: Mutex::new(cookie),
+            buffer_size: DEFAULT_BUFFER_SIZE,
+        })
+    }
+
+    /// Set the number of leading bytes `detect_file`/`detect_reader` read
+    /// before sniffing. Defaults to `1024`.
+    pub fn with_buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size;
+        self
     }
 
     /// Detect Mime of a filepath.
@@ -44,24 +80,81 @@ impl MimeDetective {
         &self,
         filename: P,
     ) -> Result<mime::Mime, DetectiveError> {
-        let mime_str = self.cookie.file(filename)?;
+        let cookie = self.cookie.lock().map_err(|_| DetectiveError::Poisoned)?;
+        let mime_str = cookie.file(filename)?;
         let mime: mime::Mime = mime_str.parse()?;
         Ok(mime)
     }
 
-    /// Detect Mime of a file.
+    /// Detect Mime of a file, sniffing up to `buffer_size` leading bytes.
     pub fn detect_file(&self, file: &mut File) -> Result<mime::Mime, DetectiveError> {
-        let mut buf: [u8; 2] = [0; 2];
-        file.read_exact(&mut buf)?;
+        self.detect_reader(file)
+    }
+
+    /// Detect Mime of a reader, sniffing up to `buffer_size` leading bytes.
+    ///
+    /// Reads until the buffer is full or the reader is exhausted, so this
+    /// also works on files smaller than `buffer_size` and on readers (pipes,
+    /// sockets) that hand back fewer bytes per `read` than requested.
+    pub fn detect_reader<R: Read>(&self, reader: &mut R) -> Result<mime::Mime, DetectiveError> {
+        let mut buf = Vec::with_capacity(self.buffer_size);
+        reader.take(self.buffer_size as u64).read_to_end(&mut buf)?;
         self.detect_buffer(&buf)
     }
 
     /// Detect Mime of a buffer.
     pub fn detect_buffer(&self, buffer: &[u8]) -> Result<mime::Mime, DetectiveError> {
-        let mime_str = self.cookie.buffer(buffer)?;
+        let cookie = self.cookie.lock().map_err(|_| DetectiveError::Poisoned)?;
+        let mime_str = cookie.buffer(buffer)?;
         let mime: mime::Mime = mime_str.parse()?;
         Ok(mime)
     }
+
+    /// Detect the charset/encoding (e.g. `utf-8`, `iso-8859-1`) of a filepath.
+    pub fn detect_encoding_filepath<P: AsRef<Path>>(
+        &self,
+        filename: P,
+    ) -> Result<String, DetectiveError> {
+        let cookie = self.cookie.lock().map_err(|_| DetectiveError::Poisoned)?;
+        cookie.set_flags(flags::MIME_ENCODING)?;
+        let result = cookie.file(filename).map(String::from);
+        cookie.set_flags(flags::MIME_TYPE)?;
+        Ok(result?)
+    }
+
+    /// Detect the charset/encoding (e.g. `utf-8`, `iso-8859-1`) of a buffer.
+    pub fn detect_encoding_buffer(&self, buffer: &[u8]) -> Result<String, DetectiveError> {
+        let cookie = self.cookie.lock().map_err(|_| DetectiveError::Poisoned)?;
+        cookie.set_flags(flags::MIME_ENCODING)?;
+        let result = cookie.buffer(buffer).map(String::from);
+        cookie.set_flags(flags::MIME_TYPE)?;
+        Ok(result?)
+    }
+
+    /// Detect the Mime of a filepath, with a populated `charset` parameter
+    /// (e.g. `text/plain; charset=utf-8`).
+    pub fn detect_full_filepath<P: AsRef<Path>>(
+        &self,
+        filename: P,
+    ) -> Result<mime::Mime, DetectiveError> {
+        let cookie = self.cookie.lock().map_err(|_| DetectiveError::Poisoned)?;
+        cookie.set_flags(flags::MIME_TYPE | flags::MIME_ENCODING)?;
+        let result = cookie.file(filename).map_err(DetectiveError::from)
+            .and_then(|mime_str| Ok(mime_str.parse::<mime::Mime>()?));
+        cookie.set_flags(flags::MIME_TYPE)?;
+        result
+    }
+
+    /// Detect the Mime of a buffer, with a populated `charset` parameter
+    /// (e.g. `text/plain; charset=utf-8`).
+    pub fn detect_full_buffer(&self, buffer: &[u8]) -> Result<mime::Mime, DetectiveError> {
+        let cookie = self.cookie.lock().map_err(|_| DetectiveError::Poisoned)?;
+        cookie.set_flags(flags::MIME_TYPE | flags::MIME_ENCODING)?;
+        let result = cookie.buffer(buffer).map_err(DetectiveError::from)
+            .and_then(|mime_str| Ok(mime_str.parse::<mime::Mime>()?));
+        cookie.set_flags(flags::MIME_TYPE)?;
+        result
+    }
 }
 
 /// Represents nested error of `magic` as well as parse and io errors.
@@ -70,6 +163,8 @@ pub enum DetectiveError {
     Magic(MagicError),
     Parse(FromStrError),
     IO(io::Error),
+    /// The internal cookie mutex was poisoned by a panic in another thread.
+    Poisoned,
 }
 
 impl error::Error for DetectiveError {
@@ -78,6 +173,7 @@ impl error::Error for DetectiveError {
             DetectiveError::Magic(ref err) => err.description(),
             DetectiveError::Parse(ref err) => err.description(),
             DetectiveError::IO(ref err) => err.description(),
+            DetectiveError::Poisoned => "cookie mutex poisoned",
         }
     }
 
@@ -86,6 +182,7 @@ impl error::Error for DetectiveError {
             DetectiveError::Magic(ref err) => err.cause(),
             DetectiveError::Parse(ref err) => err.cause(),
             DetectiveError::IO(ref err) => err.cause(),
+            DetectiveError::Poisoned => None,
         }
     }
 }
@@ -96,6 +193,7 @@ impl fmt::Display for DetectiveError {
             DetectiveError::Magic(ref err) => write!(f, "MagicError: {}", err),
             DetectiveError::Parse(ref err) => write!(f, "MimeParseError: {}", err),
             DetectiveError::IO(ref err) => write!(f, "IOError: {}", err),
+            DetectiveError::Poisoned => write!(f, "PoisonedError: cookie mutex poisoned"),
         }
     }
 }
@@ -123,7 +221,7 @@ mod tests {
     use super::MimeDetective;
     use mime;
     use std::fs::File;
-    use std::io::Read;
+    use std::io::{self, Read};
 
     fn init() -> MimeDetective {
         MimeDetective::new().expect("mime db not found")
@@ -157,4 +255,74 @@ mod tests {
         let mime = detective.detect_buffer(&buf).unwrap();
         assert_eq!(mime::TEXT_PLAIN, mime);
     }
+
+    #[test]
+    fn detect_encoding_filepath() {
+        let detective = init();
+        let encoding = detective.detect_encoding_filepath("Cargo.toml").unwrap();
+        assert_eq!("us-ascii", encoding);
+    }
+
+    #[test]
+    fn detect_encoding_buffer() {
+        let detective = init();
+        let mut file = read_file();
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf).unwrap();
+        let encoding = detective.detect_encoding_buffer(&buf).unwrap();
+        assert_eq!("us-ascii", encoding);
+    }
+
+    #[test]
+    fn detect_full_filepath() {
+        let detective = init();
+        let mime = detective.detect_full_filepath("Cargo.toml").unwrap();
+        assert_eq!((mime::TEXT, mime::PLAIN), (mime.type_(), mime.subtype()));
+        assert_eq!(
+            Some("us-ascii"),
+            mime.get_param(mime::CHARSET).map(|charset| charset.as_str())
+        );
+    }
+
+    #[test]
+    fn detect_full_buffer() {
+        let detective = init();
+        let mut file = read_file();
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf).unwrap();
+        let mime = detective.detect_full_buffer(&buf).unwrap();
+        assert_eq!((mime::TEXT, mime::PLAIN), (mime.type_(), mime.subtype()));
+        assert_eq!(
+            Some("us-ascii"),
+            mime.get_param(mime::CHARSET).map(|charset| charset.as_str())
+        );
+    }
+
+    #[test]
+    fn detect_reader_respects_buffer_size() {
+        let detective = init().with_buffer_size(2);
+        let mut file = read_file();
+        let mime = detective.detect_reader(&mut file).unwrap();
+        assert_eq!(mime::TEXT_PLAIN, mime);
+        assert_eq!(2, io::Seek::seek(&mut file, io::SeekFrom::Current(0)).unwrap());
+    }
+
+    /// A reader that only ever hands back a single byte per `read` call,
+    /// regardless of how much buffer space it's offered.
+    struct OneByteAtATime<R>(R);
+
+    impl<R: Read> Read for OneByteAtATime<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = 1.min(buf.len());
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    #[test]
+    fn detect_reader_handles_short_reads() {
+        let detective = init();
+        let mut reader = OneByteAtATime(read_file());
+        let mime = detective.detect_reader(&mut reader).unwrap();
+        assert_eq!(mime::TEXT_PLAIN, mime);
+    }
 }
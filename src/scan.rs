@@ -0,0 +1,158 @@
+use crate::{DetectiveError, Findings, MimeDetective};
+#[cfg(feature = "multi-threaded")]
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Number of leading bytes `scan` reads per file before sniffing.
+///
+/// Deliberately larger (and independent of) `MimeDetective`'s configurable
+/// `buffer_size` used by `detect_file`/`detect_reader`: a directory audit
+/// should catch signatures that live further into a file's header (e.g. the
+/// ISO 9660 volume descriptor at byte 32769) without forcing every other
+/// caller of `detect_file` to pay for a bigger sniff on every call.
+const SCAN_SNIFF_BYTES: usize = 64 * 1024;
+
+/// An error encountered while scanning a directory tree.
+///
+/// The `Read`/`Undetectable` variants carry the offending path so failures
+/// can be reported without a separate tuple.
+#[derive(Debug)]
+pub enum ScanError {
+    /// A directory entry couldn't be walked, or a file couldn't be opened
+    /// or read.
+    Read(PathBuf, io::Error),
+    /// libmagic returned no usable result for the file.
+    Undetectable(PathBuf),
+    /// The detective's cookie mutex was poisoned by a panic in another
+    /// thread; the detective is no longer usable.
+    Poisoned,
+}
+
+impl MimeDetective {
+    /// Recursively walk a directory tree and run [`detect_findings`]-style
+    /// validation on every regular file found.
+    ///
+    /// Each file is sniffed from its leading `SCAN_SNIFF_BYTES` bytes rather
+    /// than through `detect_file`'s configurable `buffer_size`, so a scan's
+    /// detection accuracy doesn't shrink when callers lower `buffer_size` for
+    /// unrelated reasons.
+    ///
+    /// With the `multi-threaded` feature enabled, files are processed in
+    /// parallel via `rayon`; otherwise they're processed sequentially.
+    ///
+    /// [`detect_findings`]: struct.MimeDetective.html#method.detect_findings
+    pub fn scan<P: AsRef<Path>>(&self, root: P) -> Vec<Result<Findings, ScanError>> {
+        let entries: Vec<Result<PathBuf, ScanError>> = WalkDir::new(root)
+            .into_iter()
+            .filter_map(|entry| match entry {
+                Ok(entry) if entry.file_type().is_file() => Some(Ok(entry.into_path())),
+                Ok(_) => None,
+                Err(err) => {
+                    let path = err.path().map(Path::to_path_buf).unwrap_or_default();
+                    Some(Err(ScanError::Read(path, err.into())))
+                }
+            })
+            .collect();
+
+        #[cfg(feature = "multi-threaded")]
+        {
+            entries.into_par_iter().map(scan_one(self)).collect()
+        }
+        #[cfg(not(feature = "multi-threaded"))]
+        {
+            entries.into_iter().map(scan_one(self)).collect()
+        }
+    }
+}
+
+fn scan_one(
+    detective: &MimeDetective,
+) -> impl Fn(Result<PathBuf, ScanError>) -> Result<Findings, ScanError> + '_ {
+    move |entry| {
+        let path = entry?;
+        let mut file = File::open(&path).map_err(|err| ScanError::Read(path.clone(), err))?;
+
+        let mut buf = Vec::with_capacity(SCAN_SNIFF_BYTES);
+        (&mut file)
+            .take(SCAN_SNIFF_BYTES as u64)
+            .read_to_end(&mut buf)
+            .map_err(|err| ScanError::Read(path.clone(), err))?;
+
+        let detected_mime = detective
+            .detect_buffer(&buf)
+            .map_err(|err| map_detect_error(path.clone(), err))?;
+        Ok(Findings::from_detected(path, detected_mime))
+    }
+}
+
+/// Translate a detection failure into a `ScanError`, keeping a poisoned
+/// mutex distinguishable from a plain "libmagic found nothing" miss.
+fn map_detect_error(path: PathBuf, err: DetectiveError) -> ScanError {
+    match err {
+        DetectiveError::IO(io_err) => ScanError::Read(path, io_err),
+        DetectiveError::Poisoned => ScanError::Poisoned,
+        DetectiveError::Magic(_) | DetectiveError::Parse(_) => ScanError::Undetectable(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{map_detect_error, ScanError};
+    use crate::{DetectiveError, MimeDetective};
+    use std::fs;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    #[test]
+    fn io_error_maps_to_read() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        match map_detect_error(PathBuf::from("f"), DetectiveError::IO(io_err)) {
+            ScanError::Read(path, _) => assert_eq!(path, PathBuf::from("f")),
+            other => panic!("expected ScanError::Read, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn poisoned_mutex_does_not_become_undetectable() {
+        match map_detect_error(PathBuf::from("f"), DetectiveError::Poisoned) {
+            ScanError::Poisoned => {}
+            other => panic!("expected ScanError::Poisoned, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn scan_detects_signatures_beyond_one_kilobyte() {
+        let dir = std::env::temp_dir().join(format!(
+            "mime-detective-scan-test-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("image.iso");
+
+        // ISO 9660 volume descriptors start at byte offset 32769 (0x8001);
+        // the `CD001` marker there sits well past `detect_file`'s 1 KiB
+        // default sniff window, so this exercises `scan`'s larger
+        // `SCAN_SNIFF_BYTES` instead.
+        let mut contents = vec![0u8; 32769];
+        contents.extend_from_slice(b"CD001");
+        let mut file = fs::File::create(&path).unwrap();
+        file.write_all(&contents).unwrap();
+        drop(file);
+
+        let detective = MimeDetective::new().expect("mime db not found");
+        let full_mime = detective.detect_filepath(&path).unwrap();
+
+        let results = detective.scan(&dir);
+        let findings = results
+            .into_iter()
+            .find_map(|result| result.ok().filter(|findings| findings.path == path))
+            .expect("scan should report a finding for the iso file");
+
+        assert_eq!(findings.detected_mime, full_mime);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}